@@ -8,20 +8,96 @@
 
 use anyhow::{Context, Result};
 use colored::*;
+use futures::stream::{FuturesUnordered, StreamExt};
 use git2::Repository;
-use reqwest::Client;
+use notify_rust::Notification;
+use reqwest::{Certificate, Client, ClientBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Semaphore;
+
+/// Hard cap on concurrent job fetches when walking pipeline history, matching
+/// the parallelism typical GitLab API clients self-impose.
+const MAX_HISTORY_CONCURRENCY: usize = 32;
+/// Number of trailing lines of a failed job's trace to print inline.
+const TRACE_TAIL_LINES: usize = 50;
+
+/// Pipeline statuses GitLab will never transition out of on its own.
+const TERMINAL_STATUSES: &[&str] = &["success", "failed", "canceled", "skipped"];
+
+/// Maximum time to keep retrying a single request before giving up.
+const MAX_RETRY_ELAPSED: Duration = Duration::from_secs(120);
+/// Upper bound on the delay between individual retry attempts.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+/// Delay before the first retry attempt.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(500);
 
 #[derive(Debug, Deserialize)]
 struct GitLabConfig {
     server: String,
     access_token: String,
     project_name: String,
+    ssl_cert: Option<String>,
+    max_retries: u32,
+    poll_interval: Duration,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, PartialEq, Eq)]
+enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Default)]
+struct CliArgs {
+    watch: bool,
+    history: Option<usize>,
+    logs: bool,
+    format: OutputFormat,
+}
+
+/// Parses the handful of flags this tool supports from `std::env::args()`.
+fn parse_args() -> Result<CliArgs> {
+    let mut args = CliArgs::default();
+    let mut iter = env::args().skip(1);
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--watch" => args.watch = true,
+            "--logs" => args.logs = true,
+            "--format" => {
+                let value = iter.next().context("--format requires a value")?;
+                args.format = match value.as_str() {
+                    "json" => OutputFormat::Json,
+                    "text" => OutputFormat::Text,
+                    other => anyhow::bail!("Unknown --format value: {} (expected text or json)", other),
+                };
+            }
+            "--history" => {
+                let value = iter
+                    .next()
+                    .context("--history requires a number of pipelines")?;
+                let history: usize = value
+                    .parse()
+                    .context("--history expects a positive integer")?;
+                if history == 0 {
+                    anyhow::bail!("--history expects a positive integer");
+                }
+                args.history = Some(history);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(args)
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 struct Pipeline {
     id: u64,
     status: String,
@@ -30,12 +106,23 @@ struct Pipeline {
     ref_name: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 struct Job {
     id: u64,
     status: String,
     name: String,
     stage: String,
+    web_url: String,
+}
+
+/// The `--format json` payload: a machine-readable snapshot of a single
+/// branch's latest pipeline, suitable for scripting and CI gating.
+#[derive(Debug, Serialize)]
+struct JsonReport {
+    branch: String,
+    pipeline_id: u64,
+    status: String,
+    jobs: Vec<Job>,
 }
 
 async fn get_git_config() -> Result<GitLabConfig> {
@@ -53,14 +140,56 @@ async fn get_git_config() -> Result<GitLabConfig> {
     let project_name = config
         .get_string("gitlab.project-name")
         .context("gitlab.project-name not found in .git/config")?;
-    
+
+    let ssl_cert = config.get_string("gitlab.ssl-cert").ok();
+
+    let max_retries = config
+        .get_i32("gitlab.max-retries")
+        .ok()
+        .and_then(|n| u32::try_from(n).ok())
+        .unwrap_or(5);
+
+    let poll_interval = config
+        .get_i32("gitlab.poll-interval")
+        .ok()
+        .and_then(|n| u64::try_from(n).ok())
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(10));
+
     Ok(GitLabConfig {
         server,
         access_token,
         project_name,
+        ssl_cert,
+        max_retries,
+        poll_interval,
     })
 }
 
+/// Build a single, reusable `reqwest::Client` configured with the GitLab
+/// access token as a default header and, if `gitlab.ssl-cert` is set, a
+/// trusted custom CA certificate for self-hosted GitLab instances.
+fn build_client(config: &GitLabConfig) -> Result<Client> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(
+        "PRIVATE-TOKEN",
+        reqwest::header::HeaderValue::from_str(&config.access_token)
+            .context("access token contains invalid header characters")?,
+    );
+
+    let mut builder = ClientBuilder::new().default_headers(headers);
+
+    if let Some(cert_path) = &config.ssl_cert {
+        let cert_bytes = std::fs::read(cert_path)
+            .with_context(|| format!("Failed to read gitlab.ssl-cert file at {}", cert_path))?;
+        let cert = Certificate::from_pem(&cert_bytes)
+            .context("Failed to parse gitlab.ssl-cert as a PEM certificate")?;
+        builder = builder.add_root_certificate(cert);
+    }
+
+    builder.build().context("Failed to build HTTP client")
+}
+
 async fn get_current_branch() -> Result<String> {
     let repo = Repository::open(".").context("Failed to open git repository")?;
     let head = repo.head().context("Failed to get HEAD")?;
@@ -71,62 +200,275 @@ async fn get_current_branch() -> Result<String> {
     Ok(branch_name)
 }
 
-async fn get_pipeline_status(config: &GitLabConfig, branch: &str) -> Result<Vec<Pipeline>> {
-    let client = Client::new();
+/// Returns a pseudo-random jitter in `0..max`, seeded from the current time.
+/// Good enough to desynchronize retries without pulling in a `rand` dependency.
+fn jitter(max: Duration) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let max_millis = max.as_millis().max(1) as u32;
+    Duration::from_millis((nanos % max_millis) as u64)
+}
+
+/// GETs `url`, retrying on connection errors and 429/5xx responses with
+/// exponential backoff (honoring `Retry-After` when the server sends one).
+/// Other 4xx responses fail immediately. Gives up once `max_retries` retries
+/// (after the first request) or `MAX_RETRY_ELAPSED` total time have been exhausted.
+async fn get_with_retry(client: &Client, url: &str, max_retries: u32) -> Result<Response> {
+    let start = Instant::now();
+    let mut delay = INITIAL_RETRY_DELAY;
+    let mut attempt = 0;
+
+    loop {
+        let result = client.get(url).send().await;
+
+        let should_retry_after = match &result {
+            Ok(response) => {
+                let status = response.status();
+                if status.is_success() {
+                    return Ok(result.unwrap());
+                }
+                if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+                    response
+                        .headers()
+                        .get(reqwest::header::RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|s| s.parse::<u64>().ok())
+                        .map(Duration::from_secs)
+                } else {
+                    anyhow::bail!("GitLab API request failed: {}", status);
+                }
+            }
+            Err(e) if e.is_connect() || e.is_timeout() => None,
+            Err(_) => {
+                return result.context("Failed to send request to GitLab API");
+            }
+        };
+
+        if attempt >= max_retries || start.elapsed() >= MAX_RETRY_ELAPSED {
+            return match result {
+                Ok(response) => {
+                    anyhow::bail!("GitLab API request failed: {}", response.status())
+                }
+                Err(e) => Err(e).context("Failed to send request to GitLab API"),
+            };
+        }
+
+        let wait = should_retry_after.unwrap_or(delay).min(MAX_RETRY_DELAY);
+        tokio::time::sleep(wait).await;
+
+        attempt += 1;
+        delay = (delay * 2 + jitter(delay)).min(MAX_RETRY_DELAY);
+    }
+}
+
+async fn get_pipeline_status(
+    client: &Client,
+    config: &GitLabConfig,
+    branch: &str,
+) -> Result<Vec<Pipeline>> {
     let url = format!(
         "{}/api/v4/projects/{}/pipelines?ref={}",
         config.server.trim_end_matches('/'),
         urlencoding::encode(&config.project_name),
         branch
     );
-    
-    let response = client
-        .get(&url)
-        .header("PRIVATE-TOKEN", &config.access_token)
-        .send()
-        .await
-        .context("Failed to send request to GitLab API")?;
-    
-    if !response.status().is_success() {
-        anyhow::bail!("GitLab API request failed: {}", response.status());
-    }
-    
+
+    let response = get_with_retry(client, &url, config.max_retries).await?;
+
     let pipelines: Vec<Pipeline> = response
         .json()
         .await
         .context("Failed to parse pipeline response")?;
-    
+
     Ok(pipelines)
 }
 
-async fn get_jobs(config: &GitLabConfig, pipeline_id: u64) -> Result<Vec<Job>> {
-    let client = Client::new();
+async fn get_jobs(client: &Client, config: &GitLabConfig, pipeline_id: u64) -> Result<Vec<Job>> {
     let url = format!(
         "{}/api/v4/projects/{}/pipelines/{}/jobs",
         config.server.trim_end_matches('/'),
         urlencoding::encode(&config.project_name),
         pipeline_id
     );
-    
-    let response = client
-        .get(&url)
-        .header("PRIVATE-TOKEN", &config.access_token)
-        .send()
-        .await
-        .context("Failed to send request to GitLab API")?;
-    
-    if !response.status().is_success() {
-        anyhow::bail!("GitLab API request failed: {}", response.status());
-    }
-    
+
+    let response = get_with_retry(client, &url, config.max_retries).await?;
+
     let jobs: Vec<Job> = response
         .json()
         .await
         .context("Failed to parse jobs response")?;
-    
+
     Ok(jobs)
 }
 
+/// GETs the raw trace log for a single job.
+async fn get_job_trace(client: &Client, config: &GitLabConfig, job_id: u64) -> Result<String> {
+    let url = format!(
+        "{}/api/v4/projects/{}/jobs/{}/trace",
+        config.server.trim_end_matches('/'),
+        urlencoding::encode(&config.project_name),
+        job_id
+    );
+
+    let response = get_with_retry(client, &url, config.max_retries).await?;
+
+    response
+        .text()
+        .await
+        .context("Failed to read job trace response")
+}
+
+/// Prints the trailing `TRACE_TAIL_LINES` lines of a job trace, preserving
+/// any ANSI color codes GitLab embedded in the raw log.
+fn print_trace_tail(trace: &str) {
+    let lines: Vec<&str> = trace.lines().collect();
+    let start = lines.len().saturating_sub(TRACE_TAIL_LINES);
+    for line in &lines[start..] {
+        println!("    {}", line);
+    }
+}
+
+/// Fetches jobs for each of `pipelines` concurrently, bounded by a semaphore
+/// so we don't hammer the GitLab API when walking a deep history.
+async fn fetch_history_jobs(
+    client: &Client,
+    config: &GitLabConfig,
+    pipelines: &[Pipeline],
+) -> Result<HashMap<u64, Vec<Job>>> {
+    let semaphore = Arc::new(Semaphore::new(pipelines.len().clamp(1, MAX_HISTORY_CONCURRENCY)));
+    let mut futures = FuturesUnordered::new();
+
+    for pipeline in pipelines {
+        let semaphore = Arc::clone(&semaphore);
+        let pipeline_id = pipeline.id;
+        futures.push(async move {
+            let _permit = semaphore.acquire().await.expect("semaphore closed");
+            let jobs = get_jobs(client, config, pipeline_id).await;
+            (pipeline_id, jobs)
+        });
+    }
+
+    let mut results = HashMap::with_capacity(pipelines.len());
+    while let Some((pipeline_id, jobs)) = futures.next().await {
+        results.insert(pipeline_id, jobs?);
+    }
+
+    Ok(results)
+}
+
+fn is_terminal_status(status: &str) -> bool {
+    TERMINAL_STATUSES.contains(&status.to_lowercase().as_str())
+}
+
+/// Maps a pipeline status to a process exit code: 0 for success, non-zero
+/// for failed/canceled, 0 otherwise (e.g. still running, or skipped).
+fn exit_code_for_status(status: &str) -> i32 {
+    match status.to_lowercase().as_str() {
+        "failed" => 1,
+        "canceled" => 2,
+        _ => 0,
+    }
+}
+
+/// Whether the human-readable "Branch: ..." banner should be printed.
+/// `--format json` must emit nothing but the JSON payload on stdout.
+fn prints_text_banner(format: &OutputFormat) -> bool {
+    *format != OutputFormat::Json
+}
+
+async fn print_pipeline_report(
+    client: &Client,
+    config: &GitLabConfig,
+    pipeline: &Pipeline,
+    jobs: &[Job],
+    show_logs: bool,
+) -> Result<()> {
+    println!("Pipeline ID: {}", pipeline.id);
+    println!("Status: ");
+    display_status(&pipeline.status);
+
+    if !jobs.is_empty() {
+        println!("\nJobs:");
+        for job in jobs {
+            print!("  {} ({}) - ", job.name, job.stage);
+            display_status(&job.status);
+
+            let failed = job.status.to_lowercase() == "failed";
+            if failed {
+                println!("    {}", job.web_url.blue().underline());
+            }
+
+            if failed && show_logs {
+                match get_job_trace(client, config, job.id).await {
+                    Ok(trace) => print_trace_tail(&trace),
+                    Err(e) => println!("    {}", format!("Failed to fetch trace: {}", e).red()),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Polls the current branch's latest pipeline every `config.poll_interval`,
+/// redrawing the report in place, until the pipeline reaches a terminal
+/// status, then fires a desktop notification summarizing the result.
+async fn watch_pipeline(
+    client: &Client,
+    config: &GitLabConfig,
+    branch: &str,
+    show_logs: bool,
+) -> Result<String> {
+    loop {
+        let pipelines = get_pipeline_status(client, config, branch).await?;
+        let Some(latest_pipeline) = pipelines.first() else {
+            println!("{}", "No pipelines found for this branch".yellow());
+            return Ok("unknown".to_string());
+        };
+
+        let jobs = get_jobs(client, config, latest_pipeline.id).await?;
+
+        // Clear the screen and redraw the report in place.
+        print!("\x1B[2J\x1B[1;1H");
+        println!("Branch: {}", branch.cyan());
+        print_pipeline_report(client, config, latest_pipeline, &jobs, show_logs).await?;
+
+        if is_terminal_status(&latest_pipeline.status) {
+            let failed_jobs: Vec<&str> = jobs
+                .iter()
+                .filter(|j| j.status.to_lowercase() == "failed")
+                .map(|j| j.name.as_str())
+                .collect();
+
+            let body = if failed_jobs.is_empty() {
+                format!("Pipeline {}", latest_pipeline.status)
+            } else {
+                format!(
+                    "Pipeline {} - failed jobs: {}",
+                    latest_pipeline.status,
+                    failed_jobs.join(", ")
+                )
+            };
+
+            // A missing notification daemon (common over SSH, in containers, or in
+            // headless CI) shouldn't turn an otherwise-successful watch into a failure.
+            if let Err(e) = Notification::new()
+                .summary(&format!("gitlab-ci-status: {}", branch))
+                .body(&body)
+                .show()
+            {
+                eprintln!("{}", format!("Failed to show desktop notification: {}", e).yellow());
+            }
+
+            return Ok(latest_pipeline.status.clone());
+        }
+
+        tokio::time::sleep(config.poll_interval).await;
+    }
+}
+
 fn display_status(status: &str) {
     match status.to_lowercase().as_str() {
         "success" => println!("{}", "● SUCCESS".green().bold()),
@@ -148,35 +490,95 @@ async fn main() -> Result<()> {
     
     // Get GitLab configuration from .git/config
     let config = get_git_config().await?;
-    
+
+    // Build a single, reusable HTTP client so connections are pooled across requests
+    let client = build_client(&config)?;
+
     // Get current branch
     let branch = get_current_branch().await?;
-    println!("Branch: {}", branch.cyan());
-    
+
+    let cli = parse_args()?;
+
+    if cli.format == OutputFormat::Json && (cli.watch || cli.history.is_some()) {
+        anyhow::bail!("--format json is not supported together with --watch or --history");
+    }
+
+    if cli.watch {
+        watch_pipeline(&client, &config, &branch, cli.logs).await?;
+        return Ok(());
+    }
+
+    if prints_text_banner(&cli.format) {
+        println!("Branch: {}", branch.cyan());
+    }
+
     // Get pipeline status
-    let pipelines = get_pipeline_status(&config, &branch).await?;
-    
+    let pipelines = get_pipeline_status(&client, &config, &branch).await?;
+
+    if pipelines.is_empty() && cli.format == OutputFormat::Json {
+        eprintln!("{}", "No pipelines found for this branch".yellow());
+        return Ok(());
+    }
+
     if pipelines.is_empty() {
         println!("{}", "No pipelines found for this branch".yellow());
         return Ok(());
     }
-    
+
+    if let Some(history) = cli.history {
+        let selected = &pipelines[..history.min(pipelines.len())];
+        let mut jobs_by_pipeline = fetch_history_jobs(&client, &config, selected).await?;
+
+        for pipeline in selected {
+            println!();
+            let jobs = jobs_by_pipeline.remove(&pipeline.id).unwrap_or_default();
+            print_pipeline_report(&client, &config, pipeline, &jobs, cli.logs).await?;
+        }
+
+        return Ok(());
+    }
+
     // Get the latest pipeline
     let latest_pipeline = &pipelines[0];
-    println!("Pipeline ID: {}", latest_pipeline.id);
-    println!("Status: ");
-    display_status(&latest_pipeline.status);
-    
+
     // Get jobs for the latest pipeline
-    let jobs = get_jobs(&config, latest_pipeline.id).await?;
-    
-    if !jobs.is_empty() {
-        println!("\nJobs:");
-        for job in jobs {
-            print!("  {} ({}) - ", job.name, job.stage);
-            display_status(&job.status);
-        }
+    let jobs = get_jobs(&client, &config, latest_pipeline.id).await?;
+
+    if cli.format == OutputFormat::Json {
+        let exit_code = exit_code_for_status(&latest_pipeline.status);
+        let report = JsonReport {
+            branch,
+            pipeline_id: latest_pipeline.id,
+            status: latest_pipeline.status.clone(),
+            jobs,
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        // Gating the exit code on pipeline status is part of --format json's contract
+        // (so it can be used in shell pipelines/CI gates); the plain text output keeps
+        // the tool's original always-exits-0 behavior.
+        std::process::exit(exit_code);
     }
-    
+
+    print_pipeline_report(&client, &config, latest_pipeline, &jobs, cli.logs).await?;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_format_suppresses_the_text_banner() {
+        assert!(!prints_text_banner(&OutputFormat::Json));
+        assert!(prints_text_banner(&OutputFormat::Text));
+    }
+
+    #[test]
+    fn exit_code_reflects_terminal_pipeline_status() {
+        assert_eq!(exit_code_for_status("success"), 0);
+        assert_eq!(exit_code_for_status("failed"), 1);
+        assert_eq!(exit_code_for_status("canceled"), 2);
+        assert_eq!(exit_code_for_status("running"), 0);
+    }
+}